@@ -0,0 +1,130 @@
+use actix_web::dev::Payload;
+use actix_web::{web, FromRequest, HttpRequest};
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::Serialize;
+use sqlx::postgres::PgPool;
+use std::future::Future;
+use std::pin::Pin;
+
+#[derive(Debug, Serialize)]
+pub struct NewApiKey {
+    pub token: String,
+}
+
+/// Generates a new API key for `namespace`, stores its argon2 hash, and
+/// returns the plaintext token. The plaintext is never persisted, so this
+/// is the only time the caller will see it.
+pub async fn create_api_key(pool: &PgPool, namespace: &str) -> Result<String, sqlx::Error> {
+    let token: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect();
+
+    let salt = SaltString::generate(&mut OsRng);
+    let key_hash = Argon2::default()
+        .hash_password(token.as_bytes(), &salt)
+        .expect("argon2 hashing failed")
+        .to_string();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO api_keys (namespace, key_hash)
+        VALUES ($1, $2)
+        "#,
+        namespace,
+        key_hash
+    )
+        .execute(pool)
+        .await?;
+
+    Ok(token)
+}
+
+/// Returns whether `namespace` already has at least one API key issued.
+/// Used to let the very first key for a namespace be claimed anonymously
+/// while requiring an existing key to mint any key after that.
+pub async fn namespace_has_keys(pool: &PgPool, namespace: &str) -> Result<bool, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT EXISTS(SELECT 1 FROM api_keys WHERE namespace = $1) AS "exists!"
+        "#,
+        namespace
+    )
+        .fetch_one(pool)
+        .await?;
+
+    Ok(row.exists)
+}
+
+/// Checks `token` against every stored hash for `namespace`, returning
+/// `true` on the first match.
+pub(crate) async fn verify_api_key(pool: &PgPool, namespace: &str, token: &str) -> Result<bool, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT key_hash FROM api_keys WHERE namespace = $1
+        "#,
+        namespace
+    )
+        .fetch_all(pool)
+        .await?;
+
+    let argon2 = Argon2::default();
+    for row in rows {
+        if let Ok(parsed_hash) = PasswordHash::new(&row.key_hash) {
+            if argon2.verify_password(token.as_bytes(), &parsed_hash).is_ok() {
+                return Ok(true);
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+/// Extractor that guards a route behind `Authorization: Bearer <token>`,
+/// verified against the `api_keys` stored for the namespace in the path.
+pub struct ApiKeyAuth;
+
+impl FromRequest for ApiKeyAuth {
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let req = req.clone();
+
+        Box::pin(async move {
+            let namespace = req
+                .match_info()
+                .get("namespace")
+                .ok_or_else(|| actix_web::error::ErrorBadRequest("Missing namespace"))?
+                .to_string();
+
+            let token = req
+                .headers()
+                .get("Authorization")
+                .and_then(|h| h.to_str().ok())
+                .and_then(|h| h.strip_prefix("Bearer "))
+                .ok_or_else(|| actix_web::error::ErrorUnauthorized("Missing bearer token"))?
+                .to_string();
+
+            let pool = req
+                .app_data::<web::Data<PgPool>>()
+                .expect("PgPool not configured as app_data")
+                .clone();
+
+            let authorized = verify_api_key(pool.get_ref(), &namespace, &token)
+                .await
+                .map_err(actix_web::error::ErrorInternalServerError)?;
+
+            if authorized {
+                Ok(ApiKeyAuth)
+            } else {
+                Err(actix_web::error::ErrorUnauthorized("Invalid API key"))
+            }
+        })
+    }
+}
@@ -0,0 +1,105 @@
+use clap::Parser;
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+
+const DEFAULT_HOST: &str = "127.0.0.1";
+const DEFAULT_MAX_CONNECTIONS: u32 = 5;
+
+/// Command-line flags. Anything left unset here falls back to the
+/// corresponding environment variable, then to `config.toml`.
+#[derive(Debug, Parser)]
+#[command(name = "rust-counter", about = "A simple namespaced counter service")]
+struct Cli {
+    /// Path to a TOML config file
+    #[arg(long, default_value = "config.toml")]
+    config: PathBuf,
+
+    /// Address to bind the webserver to
+    #[arg(long)]
+    host: Option<String>,
+
+    /// Port to bind the webserver to
+    #[arg(long)]
+    port: Option<u16>,
+
+    /// Postgres connection string
+    #[arg(long)]
+    database_url: Option<String>,
+
+    /// Maximum number of connections in the database pool
+    #[arg(long)]
+    max_connections: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FileConfig {
+    webserver: Option<FileWebserverConfig>,
+    database: Option<FileDatabaseConfig>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FileWebserverConfig {
+    host: Option<String>,
+    port: Option<u16>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FileDatabaseConfig {
+    url: Option<String>,
+    max_connections: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub host: String,
+    pub port: u16,
+    pub database_url: String,
+    pub max_connections: u32,
+}
+
+/// Builds the effective configuration from, in increasing priority: a
+/// `config.toml` file, environment variables, and CLI flags.
+pub fn load() -> Config {
+    let cli = Cli::parse();
+
+    let file_config = fs::read_to_string(&cli.config)
+        .ok()
+        .map(|contents| toml::from_str::<FileConfig>(&contents).expect("Invalid config.toml"))
+        .unwrap_or_default();
+
+    let host = cli
+        .host
+        .or_else(|| std::env::var("HOST").ok())
+        .or_else(|| file_config.webserver.as_ref().and_then(|w| w.host.clone()))
+        .unwrap_or_else(|| DEFAULT_HOST.to_string());
+
+    let port = cli
+        .port
+        .or_else(|| std::env::var("PORT").ok().and_then(|v| v.parse().ok()))
+        .or_else(|| file_config.webserver.as_ref().and_then(|w| w.port))
+        .expect("PORT must be set via --port, PORT env var, or config.toml");
+
+    let database_url = cli
+        .database_url
+        .or_else(|| std::env::var("DATABASE_URL").ok())
+        .or_else(|| file_config.database.as_ref().and_then(|d| d.url.clone()))
+        .expect("DATABASE_URL must be set via --database-url, DATABASE_URL env var, or config.toml");
+
+    let max_connections = cli
+        .max_connections
+        .or_else(|| {
+            std::env::var("MAX_CONNECTIONS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+        })
+        .or_else(|| file_config.database.as_ref().and_then(|d| d.max_connections))
+        .unwrap_or(DEFAULT_MAX_CONNECTIONS);
+
+    Config {
+        host,
+        port,
+        database_url,
+        max_connections,
+    }
+}
@@ -0,0 +1,161 @@
+use actix_web::{web, HttpResponse};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::{PgPool, Postgres};
+use sqlx::Transaction;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::internal_error;
+
+#[derive(Debug, Serialize)]
+pub struct CounterEvent {
+    pub id: Uuid,
+    pub delta: i64,
+    pub new_value: i64,
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    pub from: Option<OffsetDateTime>,
+    pub to: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatsBucket {
+    pub bucket: OffsetDateTime,
+    pub event_count: i64,
+    pub net_delta: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub enum StatsInterval {
+    #[serde(rename = "hour")]
+    Hour,
+    #[serde(rename = "day")]
+    Day,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatsQuery {
+    pub from: Option<OffsetDateTime>,
+    pub to: Option<OffsetDateTime>,
+    #[serde(default = "default_interval")]
+    pub interval: StatsInterval,
+}
+
+fn default_interval() -> StatsInterval {
+    StatsInterval::Hour
+}
+
+/// Records one mutation of a counter. Call this inside the same transaction
+/// as the counter upsert/increment so the event log and the scalar value
+/// never drift apart.
+pub async fn record_event(
+    tx: &mut Transaction<'_, Postgres>,
+    namespace: &str,
+    counter_name: &str,
+    delta: i64,
+    new_value: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO counter_events (namespace, counter_name, delta, new_value)
+        VALUES ($1, $2, $3, $4)
+        "#,
+        namespace,
+        counter_name,
+        delta,
+        new_value
+    )
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(())
+}
+
+// Handler for GET requests returning the raw event log for a counter,
+// optionally bounded by a `from`/`to` time range.
+pub async fn get_history(
+    path: web::Path<(String, String)>,
+    query: web::Query<HistoryQuery>,
+    db: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let (namespace, counter_name) = path.into_inner();
+
+    let result = sqlx::query_as!(
+        CounterEvent,
+        r#"
+        SELECT id, delta, new_value, created_at
+        FROM counter_events
+        WHERE namespace = $1
+            AND counter_name = $2
+            AND created_at >= COALESCE($3::TIMESTAMPTZ, '-infinity')
+            AND created_at <= COALESCE($4::TIMESTAMPTZ, 'infinity')
+        ORDER BY created_at ASC
+        "#,
+        namespace,
+        counter_name,
+        query.from,
+        query.to
+    )
+        .fetch_all(db.get_ref())
+        .await;
+
+    match result {
+        Ok(events) => Ok(HttpResponse::Ok().json(events)),
+        Err(e) => Ok(internal_error(e)),
+    }
+}
+
+// Handler for GET requests returning per-hour/day aggregates for a counter,
+// for feeding dashboards without replaying the raw event log.
+pub async fn get_stats(
+    path: web::Path<(String, String)>,
+    query: web::Query<StatsQuery>,
+    db: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let (namespace, counter_name) = path.into_inner();
+    let bucket_width = match query.interval {
+        StatsInterval::Hour => "hour",
+        StatsInterval::Day => "day",
+    };
+
+    let result = sqlx::query!(
+        r#"
+        SELECT
+            date_trunc($5, created_at) AS "bucket!",
+            COUNT(*) AS "event_count!",
+            COALESCE(SUM(delta), 0)::BIGINT AS "net_delta!"
+        FROM counter_events
+        WHERE namespace = $1
+            AND counter_name = $2
+            AND created_at >= COALESCE($3::TIMESTAMPTZ, '-infinity')
+            AND created_at <= COALESCE($4::TIMESTAMPTZ, 'infinity')
+        GROUP BY 1
+        ORDER BY 1 ASC
+        "#,
+        namespace,
+        counter_name,
+        query.from,
+        query.to,
+        bucket_width
+    )
+        .fetch_all(db.get_ref())
+        .await;
+
+    match result {
+        Ok(rows) => {
+            let buckets: Vec<StatsBucket> = rows
+                .into_iter()
+                .map(|row| StatsBucket {
+                    bucket: row.bucket,
+                    event_count: row.event_count,
+                    net_delta: row.net_delta,
+                })
+                .collect();
+            Ok(HttpResponse::Ok().json(buckets))
+        }
+        Err(e) => Ok(internal_error(e)),
+    }
+}
@@ -0,0 +1,318 @@
+use actix_web::dev::Server;
+use actix_web::{web, App, HttpServer, HttpRequest, HttpResponse, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgPool;
+use std::net::TcpListener;
+use tracing_actix_web::{DefaultRootSpanBuilder, RootSpanBuilder, TracingLogger};
+
+pub mod auth;
+pub mod config;
+pub mod history;
+pub mod telemetry;
+pub mod test_support;
+use auth::ApiKeyAuth;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Counter {
+    count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ErrorResponse {
+    error: String,
+}
+
+/// Root span builder that tags each request's span with a generated request
+/// id and, when the matched route has one, the `namespace` path segment.
+pub struct NamespaceRootSpanBuilder;
+
+impl RootSpanBuilder for NamespaceRootSpanBuilder {
+    fn on_request_start(request: &actix_web::dev::ServiceRequest) -> tracing::Span {
+        // Path segments are only matched after routing, so `namespace` is
+        // recorded in `on_request_end`.
+        tracing::info_span!(
+            "request",
+            request_id = %uuid::Uuid::new_v4(),
+            namespace = tracing::field::Empty,
+            method = %request.method(),
+            path = %request.path(),
+        )
+    }
+
+    fn on_request_end<B>(
+        span: tracing::Span,
+        outcome: &Result<actix_web::dev::ServiceResponse<B>, actix_web::Error>,
+    ) {
+        if let Ok(response) = outcome {
+            if let Some(namespace) = response.request().match_info().get("namespace") {
+                span.record("namespace", namespace);
+            }
+        }
+        DefaultRootSpanBuilder::on_request_end(span, outcome);
+    }
+}
+
+/// Logs the underlying error with full detail and returns a generic 500
+/// response, so clients never see raw `sqlx` error strings.
+pub(crate) fn internal_error(e: impl std::fmt::Display) -> HttpResponse {
+    tracing::error!("{}", e);
+    HttpResponse::InternalServerError().json(ErrorResponse {
+        error: "Internal server error".to_string(),
+    })
+}
+
+// Handler for GET /health: confirms the process is up and the database is
+// reachable, so load balancers can tell the two states apart.
+async fn health(db: web::Data<PgPool>) -> Result<HttpResponse, actix_web::Error> {
+    match sqlx::query!("SELECT 1 AS one").fetch_one(db.get_ref()).await {
+        Ok(_) => Ok(HttpResponse::Ok().finish()),
+        Err(e) => Ok(internal_error(e)),
+    }
+}
+
+// Handler for POST requests that issues a new API key for a namespace.
+// The generated token is only ever returned here; only its hash is stored.
+// The first key for a namespace can be claimed anonymously (bootstrap);
+// every key after that requires presenting an existing valid key for the
+// namespace, so a stranger can't mint themselves a token for a namespace
+// someone else already owns.
+async fn create_key(
+    req: HttpRequest,
+    path: web::Path<String>,
+    db: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let namespace = path.into_inner();
+
+    let has_keys = match auth::namespace_has_keys(db.get_ref(), &namespace).await {
+        Ok(has_keys) => has_keys,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    if has_keys {
+        let token = req
+            .headers()
+            .get("Authorization")
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.strip_prefix("Bearer "));
+
+        let authorized = match token {
+            Some(token) => match auth::verify_api_key(db.get_ref(), &namespace, token).await {
+                Ok(authorized) => authorized,
+                Err(e) => return Ok(internal_error(e)),
+            },
+            None => false,
+        };
+
+        if !authorized {
+            return Ok(HttpResponse::Unauthorized().json(ErrorResponse {
+                error: "A valid API key for this namespace is required to issue another one".to_string(),
+            }));
+        }
+    }
+
+    match auth::create_api_key(db.get_ref(), &namespace).await {
+        Ok(token) => Ok(HttpResponse::Ok().json(auth::NewApiKey { token })),
+        Err(e) => Ok(internal_error(e)),
+    }
+}
+
+// Handler for POST requests
+async fn update_counter(
+    _auth: ApiKeyAuth,
+    path: web::Path<(String, String)>,
+    counter: web::Json<Counter>,
+    db: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let (namespace, counter_name) = path.into_inner();
+
+    let result = update_counter_and_record(db.get_ref(), &namespace, &counter_name, counter.count).await;
+
+    match result {
+        Ok(()) => Ok(HttpResponse::Ok().json(counter.into_inner())),
+        Err(e) => Ok(internal_error(e)),
+    }
+}
+
+// Upserts the counter value and records the resulting state as an event, in
+// a single transaction so the event log never drifts from the scalar value.
+async fn update_counter_and_record(
+    pool: &PgPool,
+    namespace: &str,
+    counter_name: &str,
+    new_value: i64,
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    // Lock the row (if any) before upserting so the delta we record can't
+    // race with a concurrent mutation of the same counter.
+    let old_value = sqlx::query!(
+        r#"
+        SELECT count FROM counters
+        WHERE namespace = $1 AND counter_name = $2
+        FOR UPDATE
+        "#,
+        namespace,
+        counter_name
+    )
+        .fetch_optional(&mut *tx)
+        .await?
+        .and_then(|row| row.count)
+        .unwrap_or(0);
+
+    sqlx::query!(
+        r#"
+        INSERT INTO counters (namespace, counter_name, count)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (namespace, counter_name)
+        DO UPDATE SET count = EXCLUDED.count
+        "#,
+        namespace,
+        counter_name,
+        new_value
+    )
+        .execute(&mut *tx)
+        .await?;
+
+    history::record_event(&mut tx, namespace, counter_name, new_value - old_value, new_value).await?;
+
+    tx.commit().await
+}
+
+#[derive(Debug, Deserialize)]
+struct Delta {
+    #[serde(default = "default_delta")]
+    by: i64,
+}
+
+fn default_delta() -> i64 {
+    1
+}
+
+// Handler for POST requests that atomically increments a counter
+async fn increment_counter(
+    _auth: ApiKeyAuth,
+    path: web::Path<(String, String)>,
+    delta: Option<web::Json<Delta>>,
+    db: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    apply_delta(path, delta.map(|d| d.by).unwrap_or(1), db).await
+}
+
+// Handler for POST requests that atomically decrements a counter
+async fn decrement_counter(
+    _auth: ApiKeyAuth,
+    path: web::Path<(String, String)>,
+    delta: Option<web::Json<Delta>>,
+    db: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    apply_delta(path, -delta.map(|d| d.by).unwrap_or(1), db).await
+}
+
+// Shared implementation for the increment/decrement routes: performs the
+// mutation in SQL so concurrent requests can't clobber each other's updates.
+async fn apply_delta(
+    path: web::Path<(String, String)>,
+    by: i64,
+    db: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let (namespace, counter_name) = path.into_inner();
+
+    let result = apply_delta_and_record(db.get_ref(), &namespace, &counter_name, by).await;
+
+    match result {
+        Ok(new_value) => Ok(HttpResponse::Ok().json(Counter { count: new_value })),
+        Err(e) => Ok(internal_error(e)),
+    }
+}
+
+// Atomically applies `by` to the counter and records the mutation as an
+// event, in the same transaction, returning the resulting value.
+async fn apply_delta_and_record(
+    pool: &PgPool,
+    namespace: &str,
+    counter_name: &str,
+    by: i64,
+) -> Result<i64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let row = sqlx::query!(
+        r#"
+        INSERT INTO counters (namespace, counter_name, count)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (namespace, counter_name)
+        DO UPDATE SET count = counters.count + $3
+        RETURNING count
+        "#,
+        namespace,
+        counter_name,
+        by
+    )
+        .fetch_one(&mut *tx)
+        .await?;
+    let new_value = row.count.unwrap_or(0);
+
+    history::record_event(&mut tx, namespace, counter_name, by, new_value).await?;
+
+    tx.commit().await?;
+
+    Ok(new_value)
+}
+
+// Handler for GET requests
+async fn get_counter(
+    path: web::Path<(String, String)>,
+    db: web::Data<PgPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let (namespace, counter_name) = path.into_inner();
+
+    let result = sqlx::query!(
+        r#"
+        SELECT count FROM counters
+        WHERE namespace = $1 AND counter_name = $2
+        "#,
+        namespace,
+        counter_name
+    )
+        .fetch_optional(db.get_ref())
+        .await;
+
+    match result {
+        Ok(Some(row)) => Ok(HttpResponse::Ok().json(Counter { count: row.count.unwrap_or(0) })),
+        Ok(None) => Ok(HttpResponse::NotFound().json(ErrorResponse {
+            error: "Counter not found".to_string(),
+        })),
+        Err(e) => Ok(internal_error(e)),
+    }
+}
+
+/// Initializes the database by applying any pending migrations from
+/// `migrations/`, tracked via sqlx's `_sqlx_migrations` table.
+pub async fn init_db(pool: &PgPool) -> Result<(), sqlx::migrate::MigrateError> {
+    sqlx::migrate!("./migrations").run(pool).await
+}
+
+/// Builds and starts the actix server on an already-bound `listener`, so
+/// callers (the real `main`, or a test's `spawn_app`) control the address
+/// without risking a race between choosing a port and binding it.
+pub fn run(pool: PgPool, listener: TcpListener) -> std::io::Result<Server> {
+    let server = HttpServer::new(move || {
+        App::new()
+            .wrap(TracingLogger::<NamespaceRootSpanBuilder>::new())
+            .app_data(web::Data::new(pool.clone()))
+            .route("/health", web::get().to(health))
+            // Literal segments must be registered before the dynamic
+            // `{counter}` route below, which would otherwise swallow them
+            // (actix-web matches resources in registration order).
+            .route("/api/{namespace}/keys", web::post().to(create_key))
+            .route("/api/{namespace}/{counter}/history", web::get().to(history::get_history))
+            .route("/api/{namespace}/{counter}/stats", web::get().to(history::get_stats))
+            .route("/api/{namespace}/{counter}/increment", web::post().to(increment_counter))
+            .route("/api/{namespace}/{counter}/decrement", web::post().to(decrement_counter))
+            .route("/api/{namespace}/{counter}", web::post().to(update_counter))
+            .route("/api/{namespace}/{counter}", web::get().to(get_counter))
+    })
+        .listen(listener)?
+        .run();
+
+    Ok(server)
+}
@@ -0,0 +1,75 @@
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use uuid::Uuid;
+
+/// An ephemeral database created for a single test run. Dropped (and the
+/// underlying Postgres database along with it) when the guard goes out of
+/// scope, so tests never share or leak state.
+pub struct TestDb {
+    pub pool: PgPool,
+    name: String,
+    admin_url: String,
+}
+
+/// Connects to the `postgres` maintenance database behind `base_url`,
+/// creates a uniquely named throwaway database, runs the migrator against
+/// it, and hands back a pool plus a guard that drops the database on
+/// teardown.
+pub async fn spawn_test_database(base_url: &str) -> TestDb {
+    let db_name = format!("test_{}", Uuid::new_v4().simple());
+
+    let admin_pool = PgPoolOptions::new()
+        .connect(base_url)
+        .await
+        .expect("Failed to connect to maintenance database");
+
+    sqlx::query(&format!(r#"CREATE DATABASE "{db_name}""#))
+        .execute(&admin_pool)
+        .await
+        .expect("Failed to create test database");
+
+    let test_db_url = url_with_database(base_url, &db_name);
+    let pool = PgPoolOptions::new()
+        .connect(&test_db_url)
+        .await
+        .expect("Failed to connect to test database");
+
+    crate::init_db(&pool).await.expect("Failed to run migrations");
+
+    TestDb {
+        pool,
+        name: db_name,
+        admin_url: base_url.to_string(),
+    }
+}
+
+fn url_with_database(base_url: &str, db_name: &str) -> String {
+    let prefix = base_url
+        .rsplit_once('/')
+        .map(|(prefix, _db)| prefix)
+        .expect("DATABASE_URL must include a database name");
+
+    format!("{prefix}/{db_name}")
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let admin_url = self.admin_url.clone();
+        let db_name = self.name.clone();
+
+        // `Drop` can't be async, so the teardown runs on its own thread with
+        // its own runtime; joining it keeps this call synchronous.
+        let _ = std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().expect("Failed to build teardown runtime");
+            rt.block_on(async move {
+                if let Ok(admin_pool) = PgPoolOptions::new().connect(&admin_url).await {
+                    let _ = sqlx::query(&format!(
+                        r#"DROP DATABASE IF EXISTS "{db_name}" WITH (FORCE)"#
+                    ))
+                        .execute(&admin_pool)
+                        .await;
+                }
+            });
+        })
+        .join();
+    }
+}
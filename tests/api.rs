@@ -0,0 +1,164 @@
+mod support;
+
+use support::spawn_app;
+
+async fn issue_key(client: &reqwest::Client, address: &str, namespace: &str) -> String {
+    let response = client
+        .post(format!("{address}/api/{namespace}/keys"))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    let body: serde_json::Value = response.json().await.expect("Invalid response body");
+    body["token"].as_str().expect("Missing token").to_string()
+}
+
+#[tokio::test]
+async fn post_then_get_round_trip() {
+    let app = spawn_app().await;
+    let client = reqwest::Client::new();
+    let token = issue_key(&client, &app.address, "demo").await;
+
+    let response = client
+        .post(format!("{}/api/demo/visits", app.address))
+        .bearer_auth(&token)
+        .json(&serde_json::json!({ "count": 5 }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert!(response.status().is_success());
+
+    let response = client
+        .get(format!("{}/api/demo/visits", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert!(response.status().is_success());
+
+    let body: serde_json::Value = response.json().await.expect("Invalid response body");
+    assert_eq!(body["count"], 5);
+}
+
+#[tokio::test]
+async fn health_check_reports_database_reachable() {
+    let app = spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/health", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert!(response.status().is_success());
+}
+
+#[tokio::test]
+async fn get_missing_counter_returns_404() {
+    let app = spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/api/demo/does-not-exist", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn history_records_each_mutation_with_its_delta() {
+    let app = spawn_app().await;
+    let client = reqwest::Client::new();
+    let token = issue_key(&client, &app.address, "demo").await;
+
+    client
+        .post(format!("{}/api/demo/views", app.address))
+        .bearer_auth(&token)
+        .json(&serde_json::json!({ "count": 10 }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    client
+        .post(format!("{}/api/demo/views/increment", app.address))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    client
+        .post(format!("{}/api/demo/views/decrement", app.address))
+        .bearer_auth(&token)
+        .json(&serde_json::json!({ "by": 3 }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    let response = client
+        .get(format!("{}/api/demo/views/history", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert!(response.status().is_success());
+
+    let events: Vec<serde_json::Value> = response.json().await.expect("Invalid response body");
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0]["delta"], 10);
+    assert_eq!(events[0]["new_value"], 10);
+    assert_eq!(events[1]["delta"], 1);
+    assert_eq!(events[1]["new_value"], 11);
+    assert_eq!(events[2]["delta"], -3);
+    assert_eq!(events[2]["new_value"], 8);
+}
+
+#[tokio::test]
+async fn stats_bucket_sums_net_delta_for_the_interval() {
+    let app = spawn_app().await;
+    let client = reqwest::Client::new();
+    let token = issue_key(&client, &app.address, "demo").await;
+    let url = format!("{}/api/demo/hits/increment", app.address);
+
+    for _ in 0..4 {
+        client
+            .post(&url)
+            .bearer_auth(&token)
+            .send()
+            .await
+            .expect("Failed to execute request");
+    }
+
+    let response = client
+        .get(format!("{}/api/demo/hits/stats", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert!(response.status().is_success());
+
+    let buckets: Vec<serde_json::Value> = response.json().await.expect("Invalid response body");
+    assert_eq!(buckets.len(), 1);
+    assert_eq!(buckets[0]["event_count"], 4);
+    assert_eq!(buckets[0]["net_delta"], 4);
+}
+
+#[tokio::test]
+async fn concurrent_increments_do_not_lose_updates() {
+    let app = spawn_app().await;
+    let client = reqwest::Client::new();
+    let token = issue_key(&client, &app.address, "demo").await;
+    let url = format!("{}/api/demo/hits/increment", app.address);
+
+    let requests = (0..20).map(|_| client.post(&url).bearer_auth(&token).send());
+    let responses = futures::future::join_all(requests).await;
+    for response in responses {
+        assert!(response.expect("Failed to execute request").status().is_success());
+    }
+
+    let response = client
+        .get(format!("{}/api/demo/hits", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    let body: serde_json::Value = response.json().await.expect("Invalid response body");
+    assert_eq!(body["count"], 20);
+}
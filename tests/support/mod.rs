@@ -0,0 +1,34 @@
+use rust_counter::test_support::TestDb;
+use sqlx::postgres::PgPool;
+use std::net::TcpListener;
+
+/// A running instance of the service bound to an ephemeral port, backed by
+/// a throwaway database that's dropped when `_db` goes out of scope.
+pub struct TestApp {
+    pub address: String,
+    pub pool: PgPool,
+    _db: TestDb,
+}
+
+/// Boots the real actix server on an ephemeral port, wired to a fresh,
+/// migrated, isolated database, so tests can exercise the full HTTP surface.
+pub async fn spawn_app() -> TestApp {
+    dotenv::dotenv().ok();
+    let base_url = std::env::var("DATABASE_URL")
+        .expect("DATABASE_URL must point at a Postgres maintenance database for tests");
+
+    let db = rust_counter::test_support::spawn_test_database(&base_url).await;
+    let pool = db.pool.clone();
+
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind ephemeral port");
+    let port = listener.local_addr().unwrap().port();
+
+    let server = rust_counter::run(pool.clone(), listener).expect("Failed to start server");
+    tokio::spawn(server);
+
+    TestApp {
+        address: format!("http://127.0.0.1:{port}"),
+        pool,
+        _db: db,
+    }
+}